@@ -0,0 +1,163 @@
+//! GnuPG's colon-delimited listing format, for `--format colons`.
+//!
+//! Like the text format, this is a presentation layer over
+//! [`DumpableCert`]. It covers the `pub`, `fpr`, `uid`, and `sub` records
+//! described in GnuPG's `doc/DETAILS`; fields that need GnuPG's trust
+//! database are left empty.
+
+use std::io::{self, Write};
+
+use crate::{
+    DumpableCert, DumpableKey, DumpableKeyFlags, DumpableKeyParams, DumpableSignature,
+    DumpableSignatureEntry, DumpableUserID,
+};
+
+/// Renders `cert` as `gpg --with-colons --list-keys` would.
+pub(crate) fn render_colons(out: &mut dyn Write, cert: &DumpableCert) -> io::Result<()> {
+    render_key(
+        out,
+        "pub",
+        &cert.primary_key,
+        cert.primary_binding(),
+        Some(cert),
+    )?;
+    for uid in &cert.userids {
+        render_userid(out, uid)?;
+    }
+    for key in &cert.subkeys {
+        render_key(out, "sub", key, key.binding(), None)?;
+    }
+
+    Ok(())
+}
+
+/// Renders a `pub` or `sub` record and its `fpr` record. `cert` is only
+/// given for the primary key, whose record also sums up the capabilities
+/// of the whole cert.
+fn render_key(
+    out: &mut dyn Write,
+    kind: &str,
+    key: &DumpableKey,
+    binding: Option<&DumpableSignature>,
+    cert: Option<&DumpableCert>,
+) -> io::Result<()> {
+    let mut capabilities = key
+        .key_flags
+        .as_ref()
+        .map(DumpableKeyFlags::capability_letters)
+        .unwrap_or_default();
+    if let Some(cert) = cert {
+        let usable: String = std::iter::once(&cert.primary_key)
+            .chain(&cert.subkeys)
+            .filter(|key| key.status.alive)
+            .filter_map(|key| key.key_flags.as_ref())
+            .map(DumpableKeyFlags::capability_letters)
+            .collect();
+        capabilities.extend(
+            "esca"
+                .chars()
+                .filter(|letter| usable.contains(*letter))
+                .map(|letter| letter.to_ascii_uppercase()),
+        );
+    }
+
+    record(
+        out,
+        &[
+            kind,
+            validity(key),
+            &key.security
+                .key_length
+                .map(|length| length.to_string())
+                .unwrap_or_default(),
+            &key.algorithm_id.to_string(),
+            &key.keyid,
+            &key.creation_timestamp.to_string(),
+            &timestamp(binding.and_then(|sig| sig.key_expiration_timestamp)),
+            "",
+            "",
+            "",
+            "",
+            &capabilities,
+            "",
+            "",
+            "",
+            "",
+            curve(&key.parameters).unwrap_or_default(),
+        ],
+    )?;
+    record(
+        out,
+        &["fpr", "", "", "", "", "", "", "", "", &key.fingerprint],
+    )
+}
+
+fn render_userid(out: &mut dyn Write, uid: &DumpableUserID) -> io::Result<()> {
+    let binding = uid
+        .self_signatures
+        .first()
+        .and_then(DumpableSignatureEntry::full);
+
+    record(
+        out,
+        &[
+            "uid",
+            "",
+            "",
+            "",
+            "",
+            &timestamp(binding.and_then(|sig| sig.creation_timestamp)),
+            &timestamp(binding.and_then(|sig| sig.expiration_timestamp)),
+            "",
+            "",
+            &escape(&uid.raw),
+        ],
+    )
+}
+
+/// Writes one record, with the trailing colon GnuPG always emits.
+fn record(out: &mut dyn Write, fields: &[&str]) -> io::Result<()> {
+    writeln!(out, "{}:", fields.join(":"))
+}
+
+/// GnuPG's validity letters, as far as they can be told without a trust
+/// database: revoked, expired, invalid, or unknown.
+fn validity(key: &DumpableKey) -> &'static str {
+    if key.status.revoked {
+        "r"
+    } else if key.status.expired {
+        "e"
+    } else if !key.status.alive {
+        "i"
+    } else {
+        "-"
+    }
+}
+
+fn curve(params: &DumpableKeyParams) -> Option<&str> {
+    match params {
+        DumpableKeyParams::EdDSA { curve, .. }
+        | DumpableKeyParams::ECDSA { curve, .. }
+        | DumpableKeyParams::ECDH { curve, .. } => Some(curve.as_str()),
+        _ => None,
+    }
+}
+
+fn timestamp(timestamp: Option<i64>) -> String {
+    timestamp.map(|t| t.to_string()).unwrap_or_default()
+}
+
+/// Escapes a field the way GnuPG does: colons, backslashes, and control
+/// characters become `\xNN`.
+fn escape(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        if c == ':' || c == '\\' || c.is_ascii_control() {
+            escaped.push_str(&format!("\\x{:02x}", u32::from(c)));
+        } else {
+            escaped.push(c);
+        }
+    }
+
+    escaped
+}
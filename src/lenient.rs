@@ -0,0 +1,96 @@
+//! Normalization of sloppy ASCII armor, for `--lenient-armor`.
+//!
+//! Some tools (notably a few HSM exporters) emit armor with bare CR or
+//! mixed line endings, or with the body wrapped at odd widths. This
+//! rewrites each armored block into the canonical shape before parsing.
+
+use crate::InputEncoding;
+
+/// The line length that armor bodies are rewrapped to.
+const LINE_LENGTH: usize = 64;
+
+/// Rewrites every armored block in `input` with LF line endings, trimmed
+/// lines, and a body rewrapped to [`LINE_LENGTH`] columns. Anything outside
+/// of an armored block is passed through with only its line endings
+/// changed, and binary input is returned as-is.
+pub(crate) fn normalize(input: &[u8]) -> Vec<u8> {
+    if let InputEncoding::Binary = InputEncoding::detect(input) {
+        return input.to_vec();
+    }
+
+    let text = canonicalize_line_endings(input);
+    let mut out = Vec::with_capacity(text.len());
+    let mut lines = text.split(|&b| b == b'\n').peekable();
+
+    while let Some(line) = lines.next() {
+        let begin = line.trim_ascii();
+        if !(begin.starts_with(b"-----BEGIN PGP ") && begin.ends_with(b"-----")) {
+            push_line(&mut out, line);
+            continue;
+        }
+        push_line(&mut out, begin);
+
+        // Base64 never contains a colon, so a line with one is a header,
+        // even if the blank line that should end the headers is missing.
+        while let Some(line) = lines.next_if(|line| line.contains(&b':')) {
+            push_line(&mut out, line.trim_ascii());
+        }
+        while lines.next_if(|line| line.trim_ascii().is_empty()).is_some() {}
+        push_line(&mut out, b"");
+
+        let mut body = vec![];
+        let mut checksum = None;
+        let mut footer = None;
+        for line in lines.by_ref() {
+            let line = line.trim_ascii();
+            if line.starts_with(b"-----END PGP ") {
+                footer = Some(line);
+                break;
+            } else if is_checksum(line) {
+                checksum = Some(line);
+            } else {
+                body.extend(line.iter().filter(|b| !b.is_ascii_whitespace()));
+            }
+        }
+
+        for chunk in body.chunks(LINE_LENGTH) {
+            push_line(&mut out, chunk);
+        }
+        if let Some(checksum) = checksum {
+            push_line(&mut out, checksum);
+        }
+        if let Some(footer) = footer {
+            push_line(&mut out, footer);
+        }
+    }
+
+    out
+}
+
+/// Replaces CRLF and bare CR line endings with LF.
+fn canonicalize_line_endings(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.iter().peekable();
+
+    while let Some(&b) = bytes.next() {
+        if b == b'\r' {
+            bytes.next_if_eq(&&b'\n');
+            out.push(b'\n');
+        } else {
+            out.push(b);
+        }
+    }
+
+    out
+}
+
+/// Whether `line` is an armor checksum: `=` followed by four base64
+/// characters. A body line of just padding (`=` or `==`) isn't one.
+fn is_checksum(line: &[u8]) -> bool {
+    line.len() == 5 && line[0] == b'=' && !line[1..].contains(&b'=')
+}
+
+fn push_line(out: &mut Vec<u8>, line: &[u8]) {
+    out.extend_from_slice(line);
+    out.push(b'\n');
+}
@@ -0,0 +1,48 @@
+//! A flat, wire-level view of every packet in the input.
+
+use std::io::Read;
+
+use anyhow::Result;
+use sequoia_openpgp::{
+    packet::header::BodyLength,
+    parse::{PacketParser, PacketParserResult, Parse},
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub(crate) struct DumpablePacket {
+    tag: u8,
+    name: String,
+    /// The body length from the packet header, if it's a full length
+    /// (as opposed to a partial or indeterminate one).
+    length: Option<u32>,
+    /// How deep the packet is nested inside container packets
+    /// (e.g. compressed data), with top-level packets at depth 0.
+    /// This is also Sequoia's recursion level, as shown by `sq packet dump`.
+    depth: isize,
+    /// The packet's position in the packet tree, as a list of indices.
+    path: Vec<usize>,
+}
+
+pub(crate) fn dump<R: Read + Send + Sync>(input: R) -> Result<Vec<DumpablePacket>> {
+    let mut packets = vec![];
+
+    let mut ppr = PacketParser::from_reader(input)?;
+    while let PacketParserResult::Some(pp) = ppr {
+        let header = pp.header();
+        packets.push(DumpablePacket {
+            tag: header.ctb().tag().into(),
+            name: header.ctb().tag().to_string(),
+            length: match header.length() {
+                BodyLength::Full(length) => Some(*length),
+                _ => None,
+            },
+            depth: pp.recursion_depth(),
+            path: pp.path().into(),
+        });
+
+        ppr = pp.recurse()?.1;
+    }
+
+    Ok(packets)
+}
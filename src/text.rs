@@ -0,0 +1,126 @@
+//! Human-readable rendering of a dump, for `--format text`.
+//!
+//! This is purely a presentation layer over [`DumpableCert`]: everything
+//! it shows is also in the structured formats.
+
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+};
+
+use termcolor::{Ansi, Color, ColorSpec, NoColor, WriteColor};
+
+use crate::{DumpableCert, DumpableKey, DumpableSignature, DumpableUserID};
+
+/// Whether text written to stdout should be colorized: only if stdout is
+/// a terminal and `NO_COLOR` isn't set.
+pub(crate) fn use_color() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// Renders `cert` as a tree: the fingerprint, then the primary key, user
+/// IDs, and subkeys.
+pub(crate) fn render_text(out: &mut dyn Write, cert: &DumpableCert, color: bool) -> io::Result<()> {
+    if color {
+        render(&mut Ansi::new(out), cert)
+    } else {
+        render(&mut NoColor::new(out), cert)
+    }
+}
+
+enum Node<'a> {
+    Key(&'a DumpableKey, Option<&'a DumpableSignature>),
+    UserID(&'a DumpableUserID),
+}
+
+fn render(out: &mut impl WriteColor, cert: &DumpableCert) -> io::Result<()> {
+    out.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(out, "{}", cert.fingerprint_spaced)?;
+    out.reset()?;
+
+    let nodes: Vec<_> = std::iter::once(Node::Key(&cert.primary_key, cert.primary_binding()))
+        .chain(cert.userids.iter().map(Node::UserID))
+        .chain(cert.subkeys.iter().map(|key| Node::Key(key, key.binding())))
+        .collect();
+
+    for (i, node) in nodes.iter().enumerate() {
+        let branch = if i + 1 == nodes.len() {
+            "└──"
+        } else {
+            "├──"
+        };
+        write!(out, "{branch} ")?;
+
+        match node {
+            Node::Key(key, binding) => render_key(out, key, *binding, i == 0)?,
+            Node::UserID(uid) => render_userid(out, uid)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn render_key(
+    out: &mut impl WriteColor,
+    key: &DumpableKey,
+    binding: Option<&DumpableSignature>,
+    primary: bool,
+) -> io::Result<()> {
+    label(out, if primary { "pub" } else { "sub" })?;
+    write!(out, "{} {}", key.algorithm, key.keyid)?;
+
+    if let Some(flags) = &key.key_flags {
+        write!(out, " [{}]", flags.capability_letters().to_uppercase())?;
+    }
+
+    write!(out, " created {}", date(&key.creation))?;
+
+    if let Some(expiration) = binding.and_then(|sig| sig.key_expiration.as_deref()) {
+        write!(out, " expires {}", date(expiration))?;
+    }
+
+    if key.bound == Some(false) {
+        tag(out, "unbound", Color::Red)?;
+    }
+    if key.status.revoked {
+        tag(out, "revoked", Color::Red)?;
+    }
+    if key.status.expired {
+        tag(out, "expired", Color::Red)?;
+    }
+    if key.security.weak {
+        tag(out, "weak", Color::Yellow)?;
+    }
+
+    writeln!(out)
+}
+
+fn render_userid(out: &mut impl WriteColor, uid: &DumpableUserID) -> io::Result<()> {
+    label(out, "uid")?;
+    write!(out, "{}", uid.raw)?;
+
+    if uid.primary {
+        tag(out, "primary", Color::Cyan)?;
+    }
+
+    writeln!(out)
+}
+
+fn label(out: &mut impl WriteColor, label: &str) -> io::Result<()> {
+    out.set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true))?;
+    write!(out, "{label}")?;
+    out.reset()?;
+    write!(out, " ")
+}
+
+fn tag(out: &mut impl WriteColor, tag: &str, color: Color) -> io::Result<()> {
+    write!(out, " ")?;
+    out.set_color(ColorSpec::new().set_fg(Some(color)))?;
+    write!(out, "[{tag}]")?;
+    out.reset()
+}
+
+/// The date part of an RFC 3339 timestamp.
+fn date(time: &str) -> &str {
+    time.get(..10).unwrap_or(time)
+}
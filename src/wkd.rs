@@ -0,0 +1,64 @@
+//! Web Key Directory (WKD) URL computation.
+//!
+//! See <https://datatracker.ietf.org/doc/draft-koch-openpgp-webkey-service/>.
+
+use anyhow::{bail, Result};
+use reqwest::Url;
+use sequoia_openpgp::{crypto::hash::Digest, types::HashAlgorithm};
+
+/// The z-base-32 alphabet, as used by WKD.
+const ZBASE32_ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+
+fn zbase32(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for byte in data {
+        buffer = (buffer << 8) | u16::from(*byte);
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(char::from(
+                ZBASE32_ALPHABET[usize::from((buffer >> bits) & 0x1f)],
+            ));
+        }
+    }
+
+    if bits > 0 {
+        encoded.push(char::from(
+            ZBASE32_ALPHABET[usize::from((buffer << (5 - bits)) & 0x1f)],
+        ));
+    }
+
+    encoded
+}
+
+/// Returns the WKD lookup URLs for the given email address, in the order
+/// they should be tried: the advanced method first, then the direct method.
+pub(crate) fn urls(email: &str) -> Result<[Url; 2]> {
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        bail!("not an email address: {email}");
+    };
+    if local.is_empty() || domain.is_empty() {
+        bail!("not an email address: {email}");
+    }
+
+    let domain = domain.to_lowercase();
+
+    let mut sha1 = HashAlgorithm::SHA1.context()?;
+    sha1.update(local.to_lowercase().as_bytes());
+    let hash = zbase32(&sha1.into_digest()?);
+
+    let advanced = Url::parse_with_params(
+        &format!("https://openpgpkey.{domain}/.well-known/openpgpkey/{domain}/hu/{hash}"),
+        [("l", local)],
+    )?;
+    let direct = Url::parse_with_params(
+        &format!("https://{domain}/.well-known/openpgpkey/hu/{hash}"),
+        [("l", local)],
+    )?;
+
+    Ok([advanced, direct])
+}
@@ -0,0 +1,1046 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use base64::Engine;
+use sequoia_openpgp::{
+    cert::{CertBuilder, CipherSuite},
+    crypto::hash::Digest,
+    packet::{
+        key::{Key4, PrimaryRole, SecretParts, SubordinateRole},
+        signature::SignatureBuilder,
+        Key,
+    },
+    parse::Parse,
+    policy::StandardPolicy,
+    serialize::{
+        stream::{Compressor, LiteralWriter, Message},
+        SerializeInto,
+    },
+    types::{Curve, HashAlgorithm, KeyFlags, NotationDataFlags, SignatureType},
+    Cert, Packet,
+};
+
+fn cert() -> Cert {
+    let (cert, _) = CertBuilder::general_purpose(None, Some("Alice <alice@example.org>"))
+        .generate()
+        .unwrap();
+    cert
+}
+
+fn signer(cert: &Cert) -> impl sequoia_openpgp::crypto::Signer {
+    cert.primary_key()
+        .key()
+        .clone()
+        .parts_into_secret()
+        .unwrap()
+        .into_keypair()
+        .unwrap()
+}
+
+/// Runs `pgpkeydump` with the given arguments, feeding `input` on stdin.
+fn pgpkeydump(input: &[u8], args: &[&str]) -> std::process::Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pgpkeydump"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn dump_json(input: &[u8], args: &[&str]) -> serde_json::Value {
+    let output = pgpkeydump(input, args);
+    assert!(output.status.success(), "{output:?}");
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn compact_matches_pretty() {
+    let input = cert().armored().to_vec().unwrap();
+
+    let output = pgpkeydump(&input, &["--compact"]);
+    assert!(output.status.success());
+    let compact = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(compact.trim_end().lines().count(), 1);
+
+    let compact: serde_json::Value = serde_json::from_str(&compact).unwrap();
+    assert_eq!(compact, dump_json(&input, &[]));
+}
+
+#[test]
+fn primary_only_skips_subkeys() {
+    let input = cert().armored().to_vec().unwrap();
+
+    let full = dump_json(&input, &[]);
+    assert!(!full["subkeys"].as_array().unwrap().is_empty());
+
+    let primary_only = dump_json(&input, &["--primary-only"]);
+    assert_eq!(primary_only["subkeys"], serde_json::json!([]));
+    assert_eq!(primary_only["primary_key"], full["primary_key"]);
+}
+
+#[test]
+fn armor_headers_deduped_and_suppressible() {
+    // Both user IDs get truncated to the same armor header.
+    let prefix = "A".repeat(72);
+    let (cert, _) = CertBuilder::new()
+        .add_userid(format!("{prefix} <one@example.org>"))
+        .add_userid(format!("{prefix} <two@example.org>"))
+        .generate()
+        .unwrap();
+    assert_eq!(cert.armor_headers().len(), 3);
+
+    let input = cert.armored().to_vec().unwrap();
+
+    let dump = dump_json(&input, &[]);
+    let headers = dump["armor_headers"].as_array().unwrap();
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers[0], cert.fingerprint().to_spaced_hex());
+
+    let dump = dump_json(&input, &["--no-armor-headers"]);
+    assert!(dump.get("armor_headers").is_none());
+}
+
+#[test]
+fn spaced_fingerprint() {
+    // A fixed secret and creation time give us a fixed fingerprint.
+    let key: Key<SecretParts, PrimaryRole> =
+        Key4::import_secret_ed25519(&[0x2a; 32], UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+            .unwrap()
+            .into();
+    let cert = Cert::try_from(vec![Packet::from(key.parts_into_public())]).unwrap();
+
+    let dump = dump_json(&cert.to_vec().unwrap(), &[]);
+    assert_eq!(
+        dump["fingerprint"],
+        "E9A09D1F7D3319E9E902183D4364FAA5D5821D75"
+    );
+    assert_eq!(
+        dump["fingerprint_spaced"],
+        "E9A0 9D1F 7D33 19E9 E902  183D 4364 FAA5 D582 1D75"
+    );
+    assert_eq!(
+        dump["primary_key"]["fingerprint_spaced"],
+        dump["fingerprint_spaced"]
+    );
+}
+
+#[test]
+fn fingerprint_bytes() {
+    let dump = dump_json(&cert().to_vec().unwrap(), &[]);
+
+    for dump in [&dump, &dump["primary_key"], &dump["subkeys"][0]] {
+        assert_eq!(
+            dump["fingerprint_bytes"].as_str().unwrap(),
+            dump["fingerprint"].as_str().unwrap().to_lowercase()
+        );
+    }
+}
+
+#[test]
+fn revocation_certificate() {
+    // gpg's `--gen-revoke` output, attached to the primary key it revokes.
+    let dump = dump_json(include_bytes!("data/gpg-revocation.pgp"), &[]);
+    assert_eq!(dump["is_revocation_certificate"], true);
+    assert_eq!(dump["warnings"], serde_json::json!([]));
+
+    let primary = &dump["primary_key"];
+    assert_eq!(primary["status"]["revoked"], true);
+    assert_eq!(primary["self_revocations"].as_array().unwrap().len(), 1);
+    assert_eq!(primary["self_revocations"][0]["type"], "KeyRevocation");
+    assert_eq!(
+        dump["revoked_at"],
+        primary["self_revocations"][0]["creation"]
+    );
+
+    let dump = dump_json(&cert().armored().to_vec().unwrap(), &[]);
+    assert_eq!(dump["is_revocation_certificate"], false);
+    assert!(dump["revoked_at"].is_null());
+    assert_eq!(dump["primary_key"]["can_certify"], true);
+    assert_eq!(dump["warnings"], serde_json::json!([]));
+}
+
+#[test]
+fn jobs_preserve_order() {
+    let mut input = vec![];
+    for _ in 0..20 {
+        input.extend(cert().to_vec().unwrap());
+    }
+
+    let serial = pgpkeydump(&input, &["--ndjson"]);
+    assert!(serial.status.success(), "{serial:?}");
+    let parallel = pgpkeydump(&input, &["--ndjson", "--jobs", "4"]);
+    assert!(parallel.status.success(), "{parallel:?}");
+
+    assert_eq!(String::from_utf8_lossy(&serial.stdout).lines().count(), 20);
+    assert_eq!(serial.stdout, parallel.stdout);
+}
+
+#[test]
+fn attested_certifications() {
+    let alice = cert();
+    let bob = cert();
+    let mut alice_signer = signer(&alice);
+    let mut bob_signer = signer(&bob);
+
+    let certification = alice
+        .userids()
+        .next()
+        .unwrap()
+        .userid()
+        .certify(
+            &mut bob_signer,
+            &alice,
+            SignatureType::GenericCertification,
+            None,
+            None,
+        )
+        .unwrap();
+    let alice = alice.insert_packets(certification.clone()).unwrap();
+
+    let attestations = alice
+        .userids()
+        .next()
+        .unwrap()
+        .attest_certifications2(
+            &StandardPolicy::new(),
+            None,
+            &mut alice_signer,
+            [&certification],
+        )
+        .unwrap();
+    let alice = alice.insert_packets(attestations).unwrap();
+
+    let dump = dump_json(&alice.to_vec().unwrap(), &[]);
+    let uid = &dump["userids"][0];
+    assert_eq!(uid["certifications"].as_array().unwrap().len(), 1);
+    assert_eq!(uid["attestations"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        uid["attestations"][0]["attested_certifications"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        uid["certifications"][0]["attested_certifications"],
+        serde_json::json!([])
+    );
+}
+
+#[test]
+fn lenient_armor() {
+    let cert = cert();
+    let armored = String::from_utf8(cert.armored().to_vec().unwrap()).unwrap();
+
+    // CRLF line endings, except for bare CRs in the armor headers, with the
+    // body rewrapped at an odd width.
+    let (headers, rest) = armored.split_once("\n\n").unwrap();
+    let (body, footer) = rest.rsplit_once("\n=").unwrap();
+    let body = body.replace('\n', "");
+    let body: Vec<_> = body.as_bytes().chunks(76).collect();
+    let input = format!(
+        "{}\r\r\n{}\r\n={}",
+        headers.replace('\n', "\r"),
+        String::from_utf8(body.join(&b"\r\n"[..])).unwrap(),
+        footer.replace('\n', "\r\n"),
+    );
+
+    let output = pgpkeydump(input.as_bytes(), &[]);
+    assert!(!output.status.success());
+
+    let dump = dump_json(input.as_bytes(), &["--lenient-armor"]);
+    assert_eq!(dump["fingerprint"], cert.fingerprint().to_hex());
+}
+
+#[test]
+fn content_hash_covers_signatures() {
+    let alice = cert();
+    let before = dump_json(&alice.to_vec().unwrap(), &[]);
+
+    let mut hash = HashAlgorithm::SHA256.context().unwrap();
+    hash.update(&alice.export_to_vec().unwrap());
+    assert_eq!(
+        before["content_hash"],
+        hex::encode(hash.into_digest().unwrap())
+    );
+
+    let certification = alice
+        .userids()
+        .next()
+        .unwrap()
+        .userid()
+        .certify(
+            &mut signer(&cert()),
+            &alice,
+            SignatureType::GenericCertification,
+            None,
+            None,
+        )
+        .unwrap();
+    let alice = alice.insert_packets(certification).unwrap();
+    let after = dump_json(&alice.to_vec().unwrap(), &[]);
+
+    assert_eq!(after["fingerprint"], before["fingerprint"]);
+    assert_ne!(after["content_hash"], before["content_hash"]);
+}
+
+#[test]
+fn best_effort_salvages_truncated_input() {
+    let alice = cert();
+    let input = alice.to_vec().unwrap();
+
+    let dump = dump_json(&input, &["--best-effort"]);
+    assert_eq!(dump["truncated"], false);
+    assert!(dump_json(&input, &[])["truncated"].is_null());
+
+    // Cut the last packet, a subkey binding signature, short.
+    let dump = dump_json(&input[..input.len() - 16], &["--best-effort"]);
+    assert_eq!(dump["fingerprint"], alice.fingerprint().to_hex());
+    assert_eq!(dump["truncated"], true);
+}
+
+#[test]
+fn redact() {
+    let alice = cert();
+    let userid = alice.userids().next().unwrap().userid().clone();
+    let certification = SignatureBuilder::new(SignatureType::GenericCertification)
+        .add_notation(
+            "proof@example.org",
+            "https://alice.example.org",
+            NotationDataFlags::empty().set_human_readable().unwrap(),
+            false,
+        )
+        .unwrap()
+        .set_preferred_key_server(b"hkps://keys.alice.example.org")
+        .unwrap()
+        .sign_userid_binding(&mut signer(&cert()), alice.primary_key().key(), &userid)
+        .unwrap();
+    let alice = alice.insert_packets(certification).unwrap();
+    let input = alice.armored().to_vec().unwrap();
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("redact-alice.asc");
+    std::fs::write(&path, &input).unwrap();
+
+    let output = pgpkeydump(b"", &["--redact", path.to_str().unwrap()]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(!String::from_utf8_lossy(&output.stdout)
+        .to_lowercase()
+        .contains("alice"));
+
+    let dump: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let uid = &dump["userids"][0];
+    assert_eq!(uid["raw"], "[redacted]");
+    assert_eq!(uid["email"], "[redacted]");
+    assert!(uid["comment"].is_null());
+    assert_eq!(uid["self_signatures"].as_array().unwrap().len(), 1);
+    let certification = &uid["certifications"][0];
+    assert_eq!(certification["notations"][0]["name"], "proof@example.org");
+    assert_eq!(certification["notations"][0]["value"], "[redacted]");
+    assert_eq!(certification["preferred_key_server"], "[redacted]");
+    assert_eq!(dump["source"], "[redacted]");
+    assert_eq!(dump["fingerprint"], dump_json(&input, &[])["fingerprint"]);
+
+    // `--diff` dumps user IDs straight from the certs, so it can't be
+    // redacted.
+    let other = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("redact-diff.pgp");
+    std::fs::write(&other, cert().to_vec().unwrap()).unwrap();
+    let output = pgpkeydump(&input, &["--redact", "--diff", other.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout)
+        .to_lowercase()
+        .contains("alice"));
+}
+
+#[test]
+fn multiple_input_files() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (alice, bob) = (cert(), cert());
+    let alice_path = dir.join("multiple-input-files-alice.asc");
+    let bob_path = dir.join("multiple-input-files-bob.pgp");
+    std::fs::write(&alice_path, alice.armored().to_vec().unwrap()).unwrap();
+    std::fs::write(&bob_path, bob.to_vec().unwrap()).unwrap();
+
+    let dump = dump_json(
+        b"",
+        &[alice_path.to_str().unwrap(), bob_path.to_str().unwrap()],
+    );
+    let certs = dump.as_array().unwrap();
+    assert_eq!(certs.len(), 2);
+    assert_eq!(certs[0]["fingerprint"], alice.fingerprint().to_hex());
+    assert_eq!(certs[0]["source"], alice_path.to_str().unwrap());
+    assert_eq!(certs[0]["input_encoding"], "armored");
+    assert_eq!(certs[1]["fingerprint"], bob.fingerprint().to_hex());
+    assert_eq!(certs[1]["source"], bob_path.to_str().unwrap());
+    assert_eq!(certs[1]["input_encoding"], "binary");
+}
+
+#[test]
+fn multiple_input_files_filters() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let alice = cert();
+    let paths = ["first", "second"].map(|name| {
+        let path = dir.join(format!("multiple-input-files-filters-{name}.pgp"));
+        std::fs::write(&path, alice.to_vec().unwrap()).unwrap();
+        path.to_str().unwrap().to_owned()
+    });
+
+    // Several files are a keyring, so the keyring filters apply without
+    // `--keyring`.
+    let dump = dump_json(b"", &[&paths[0], &paths[1], "--dedup"]);
+    let certs = dump.as_array().unwrap();
+    assert_eq!(certs.len(), 1);
+    assert_eq!(certs[0]["fingerprint"], alice.fingerprint().to_hex());
+
+    let dump = dump_json(b"", &[&paths[0], &paths[1], "--only-revoked"]);
+    assert_eq!(dump, serde_json::json!([]));
+
+    // A single input is a keyring only under `--keyring` or `--ndjson`.
+    let output = pgpkeydump(b"", &[&paths[0], "--dedup"]);
+    assert!(!output.status.success());
+    assert!(dump_json(b"", &[&paths[0], "--dedup", "--keyring"]).is_array());
+}
+
+#[test]
+fn colons_format() {
+    let alice = cert();
+    let output = pgpkeydump(&alice.to_vec().unwrap(), &["--format", "colons"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let output = String::from_utf8(output.stdout).unwrap();
+    let records: Vec<Vec<_>> = output.lines().map(|l| l.split(':').collect()).collect();
+    let kinds: Vec<_> = records.iter().map(|record| record[0]).collect();
+    // A certification-only primary key, with signing and encryption
+    // subkeys.
+    assert_eq!(kinds, ["pub", "fpr", "uid", "sub", "fpr", "sub", "fpr"]);
+
+    assert_eq!(records[0][4], alice.keyid().to_hex());
+    assert_eq!(records[0][11], "cESC");
+    assert_eq!(records[1][9], alice.fingerprint().to_hex());
+    assert_eq!(records[2][9], "Alice <alice@example.org>");
+    let mut subkey_capabilities = [records[3][11], records[5][11]];
+    subkey_capabilities.sort_unstable();
+    assert_eq!(subkey_capabilities, ["e", "s"]);
+}
+
+#[test]
+fn signature_time_window() {
+    let input = cert().armored().to_vec().unwrap();
+
+    let dump = dump_json(&input, &["--sig-since", "2000-01-01T00:00:00Z"]);
+    assert_eq!(
+        dump["userids"][0]["self_signatures"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+
+    assert!(dump["subkeys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|subkey| !subkey["primary_key_binding"].is_null()));
+
+    let dump = dump_json(&input, &["--sig-until", "2000-01-01T00:00:00Z"]);
+    assert!(dump["subkeys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|subkey| subkey["primary_key_binding"].is_null()));
+    assert!(dump["userids"][0]["self_signatures"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+    assert!(dump["subkeys"][0]["self_signatures"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn signature_time_window_other_modes() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let alice = cert();
+    let message = dir.join("sig-window-message.txt");
+    let signers = dir.join("sig-window-signers.pgp");
+    let other = dir.join("sig-window-other.pgp");
+    std::fs::write(&message, b"hello").unwrap();
+    std::fs::write(&signers, alice.to_vec().unwrap()).unwrap();
+    std::fs::write(&other, cert().to_vec().unwrap()).unwrap();
+
+    let sig = SignatureBuilder::new(SignatureType::Binary)
+        .sign_message(&mut signer(&alice), b"hello")
+        .unwrap();
+    let sig = Packet::from(sig).to_vec().unwrap();
+    let verify = [
+        "--verify",
+        message.to_str().unwrap(),
+        "--signer",
+        signers.to_str().unwrap(),
+    ];
+    let window = ["--sig-until", "2000-01-01T00:00:00Z"];
+
+    assert_eq!(
+        dump_json(&sig, &["--signature"]).as_array().unwrap().len(),
+        1
+    );
+    assert!(dump_json(&sig, &[&["--signature"][..], &window].concat())
+        .as_array()
+        .unwrap()
+        .is_empty());
+
+    // The primary key is certification-only, so `--verify` fails either
+    // way; only the number of verifications matters here.
+    let output = pgpkeydump(&sig, &verify);
+    let dump: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(dump.as_array().unwrap().len(), 1);
+    let output = pgpkeydump(&sig, &[&verify[..], &window].concat());
+    let dump: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(dump.as_array().unwrap().is_empty());
+
+    let input = alice.to_vec().unwrap();
+    let diff = ["--diff", other.to_str().unwrap()];
+    let dump = dump_json(&input, &diff);
+    assert!(!dump["added"]["signatures"].as_object().unwrap().is_empty());
+    let dump = dump_json(&input, &[&diff[..], &window].concat());
+    assert!(dump["added"]["signatures"].as_object().unwrap().is_empty());
+    assert!(dump["removed"]["signatures"]
+        .as_object()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn flatten_signatures() {
+    let alice = cert();
+    let dump = dump_json(&alice.to_vec().unwrap(), &["--flatten", "signatures"]);
+    let rows = dump.as_array().unwrap();
+
+    let fpr = alice.fingerprint().to_hex();
+    assert!(rows.iter().all(|row| row["cert_fingerprint"] == fpr));
+    assert!(rows
+        .iter()
+        .any(|row| row["userid"] == "Alice <alice@example.org>" && row["key_fingerprint"] == fpr));
+    assert!(rows.iter().any(|row| row["key_fingerprint"] != fpr));
+}
+
+#[test]
+fn subkey_binding_status() {
+    let input = cert().armored().to_vec().unwrap();
+
+    let dump = dump_json(&input, &[]);
+    assert!(dump["primary_key"].get("bound").is_none());
+    assert!(dump["primary_key"].get("alive").is_none());
+    let subkey = &dump["subkeys"][0];
+    assert_eq!(subkey["bound"], true);
+    assert_eq!(subkey["alive"], true);
+    assert!(subkey["key_flags"].is_object());
+
+    // Before the cert was made, nothing binds the subkey yet.
+    let dump = dump_json(&input, &["--time", "2000-01-01T00:00:00Z"]);
+    let subkey = &dump["subkeys"][0];
+    assert_eq!(subkey["bound"], false);
+    assert_eq!(subkey["alive"], false);
+    assert!(subkey["key_flags"].is_null());
+}
+
+#[test]
+fn embed_armor() {
+    let alice = cert();
+    let input = alice.to_vec().unwrap();
+    assert!(dump_json(&input, &[]).get("armored").is_none());
+
+    let dump = dump_json(&input, &["--embed-armor"]);
+    let armored = dump["armored"].as_str().unwrap();
+    assert!(armored.starts_with("-----BEGIN PGP PUBLIC KEY BLOCK-----"));
+    assert_eq!(Cert::from_bytes(armored).unwrap(), alice);
+}
+
+#[test]
+fn strict_fails_on_warnings() {
+    let input = cert().armored().to_vec().unwrap();
+
+    let output = pgpkeydump(&input, &["--strict"]);
+    assert!(output.status.success(), "{output:?}");
+
+    // Before the cert was made, none of its user IDs are valid yet.
+    let output = pgpkeydump(&input, &["--strict", "--time", "2000-01-01T00:00:00Z"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn include_raw() {
+    let alice = cert();
+    let input = alice.to_vec().unwrap();
+    let dump = dump_json(&input, &["--include-raw"]);
+
+    let raw = |value: &serde_json::Value| {
+        base64::engine::general_purpose::STANDARD
+            .decode(value["raw"].as_str().unwrap())
+            .unwrap()
+    };
+    assert_eq!(
+        raw(&dump["primary_key"]),
+        Packet::from(alice.primary_key().key().clone())
+            .to_vec()
+            .unwrap()
+    );
+    let sig = alice
+        .userids()
+        .next()
+        .unwrap()
+        .self_signatures()
+        .next()
+        .unwrap();
+    assert_eq!(
+        raw(&dump["userids"][0]["self_signatures"][0]),
+        Packet::from(sig.clone()).to_vec().unwrap()
+    );
+    assert!(dump_json(&input, &[])["primary_key"].get("raw").is_none());
+}
+
+#[test]
+fn primary_userid_flag() {
+    let dump = dump_json(&cert().to_vec().unwrap(), &[]);
+    let binding = &dump["userids"][0]["self_signatures"][0];
+    assert_eq!(binding["primary_userid_flag"], true);
+    assert!(dump["subkeys"][0]["self_signatures"][0]["primary_userid_flag"].is_null());
+}
+
+#[test]
+fn keyring_json_array() {
+    let (alice, bob) = (cert(), cert());
+    let mut keyring = alice.to_vec().unwrap();
+    keyring.extend(bob.to_vec().unwrap());
+
+    for args in [&["--keyring"][..], &["--keyring", "--compact"][..]] {
+        let dump = dump_json(&keyring, args);
+        let certs = dump.as_array().unwrap();
+        assert_eq!(certs.len(), 2);
+        assert_eq!(certs[0]["fingerprint"], alice.fingerprint().to_hex());
+        assert_eq!(certs[1]["fingerprint"], bob.fingerprint().to_hex());
+
+        let dump = dump_json(&alice.to_vec().unwrap(), args);
+        assert_eq!(dump.as_array().unwrap().len(), 1);
+
+        let output = pgpkeydump(
+            &keyring,
+            &[args, &["--select", "0000000000000000"][..]].concat(),
+        );
+        assert!(output.status.success(), "{output:?}");
+        assert_eq!(output.stdout, b"[]\n");
+    }
+
+    let pretty = dump_json(&keyring, &["--keyring"]);
+    assert_eq!(pretty, dump_json(&keyring, &["--keyring", "--compact"]));
+}
+
+#[test]
+fn subpacket_tags() {
+    let dump = dump_json(&cert().to_vec().unwrap(), &[]);
+    let tags: Vec<_> = dump["userids"][0]["self_signatures"][0]["subpacket_tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| tag.as_u64().unwrap())
+        .collect();
+
+    // Signature Creation Time, Issuer Fingerprint, and Issuer.
+    for tag in [2, 33, 16] {
+        assert!(tags.contains(&tag), "{tags:?}");
+    }
+}
+
+#[test]
+fn fingerprints_only() {
+    let (alice, bob) = (cert(), cert());
+    let mut keyring = alice.to_vec().unwrap();
+    keyring.extend(bob.to_vec().unwrap());
+
+    let output = pgpkeydump(&keyring, &["--fingerprints-only"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "{}\n{}\n",
+            alice.fingerprint().to_hex(),
+            bob.fingerprint().to_hex()
+        )
+    );
+
+    let output = pgpkeydump(&keyring, &["--fingerprints-only", "--with-subkeys"]);
+    assert!(output.status.success(), "{output:?}");
+    let expected: Vec<_> = [&alice, &bob]
+        .into_iter()
+        .flat_map(|cert| cert.keys().map(|key| key.fingerprint().to_hex()))
+        .collect();
+    assert_eq!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .collect::<Vec<_>>(),
+        expected
+    );
+}
+
+#[test]
+fn missing_backsig() {
+    let alice = cert();
+    let dump = dump_json(&alice.to_vec().unwrap(), &[]);
+    assert!(dump["primary_key"].get("missing_backsig").is_none());
+    assert_eq!(dump["subkeys"][0]["missing_backsig"], false);
+    assert_eq!(dump["subkeys"][0]["invalid_backsig"], false);
+
+    // A signing subkey bound without the back-signature it needs.
+    let subkey: Key<SecretParts, SubordinateRole> =
+        Key4::generate_ecc(true, Curve::Ed25519).unwrap().into();
+    let subkey = subkey.parts_into_public();
+    let binding = SignatureBuilder::new(SignatureType::SubkeyBinding)
+        .set_key_flags(KeyFlags::empty().set_signing())
+        .unwrap()
+        .sign_subkey_binding(&mut signer(&alice), alice.primary_key().key(), &subkey)
+        .unwrap();
+    let alice = alice
+        .insert_packets(vec![Packet::from(subkey.clone()), binding.into()])
+        .unwrap();
+
+    let dump = dump_json(&alice.to_vec().unwrap(), &[]);
+    let dumped = dump["subkeys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|key| key["fingerprint"] == subkey.fingerprint().to_hex())
+        .unwrap();
+    assert_eq!(dumped["missing_backsig"], true);
+    assert!(dump["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|warning| warning
+            .as_str()
+            .unwrap()
+            .starts_with("signing subkeys without")));
+}
+
+#[test]
+fn invalid_backsig() {
+    let alice = cert();
+
+    // A signing subkey whose back-signature was made by some other key, so
+    // it doesn't verify.
+    let subkey: Key<SecretParts, SubordinateRole> =
+        Key4::generate_ecc(true, Curve::Ed25519).unwrap().into();
+    let impostor: Key<SecretParts, SubordinateRole> =
+        Key4::generate_ecc(true, Curve::Ed25519).unwrap().into();
+    let backsig = SignatureBuilder::new(SignatureType::PrimaryKeyBinding)
+        .sign_primary_key_binding(
+            &mut impostor.into_keypair().unwrap(),
+            alice.primary_key().key(),
+            &subkey,
+        )
+        .unwrap();
+    let subkey = subkey.parts_into_public();
+    let binding = SignatureBuilder::new(SignatureType::SubkeyBinding)
+        .set_key_flags(KeyFlags::empty().set_signing())
+        .unwrap()
+        .set_embedded_signature(backsig)
+        .unwrap()
+        .sign_subkey_binding(&mut signer(&alice), alice.primary_key().key(), &subkey)
+        .unwrap();
+    let alice = alice
+        .insert_packets(vec![Packet::from(subkey.clone()), binding.into()])
+        .unwrap();
+
+    let dump = dump_json(&alice.to_vec().unwrap(), &[]);
+    let dumped = dump["subkeys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|key| key["fingerprint"] == subkey.fingerprint().to_hex())
+        .unwrap();
+    assert_eq!(dumped["missing_backsig"], false);
+    assert_eq!(dumped["invalid_backsig"], true);
+    assert!(dump["warnings"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!(format!(
+            "signing subkeys without a valid back-signature: {}",
+            subkey.keyid().to_hex()
+        ))));
+}
+
+#[test]
+fn timezone() {
+    let input = cert().to_vec().unwrap();
+    let utc = dump_json(&input, &[]);
+    let offset = dump_json(&input, &["--timezone", "+05:30"]);
+
+    let creation = offset["primary_key"]["creation"].as_str().unwrap();
+    assert!(creation.ends_with("+05:30"), "{creation}");
+    assert!(utc["primary_key"]["creation"]
+        .as_str()
+        .unwrap()
+        .ends_with("+00:00"));
+    assert_eq!(
+        offset["primary_key"]["creation_timestamp"],
+        utc["primary_key"]["creation_timestamp"]
+    );
+
+    let output = pgpkeydump(&input, &["--timezone", "mars"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn duplicate_subkeys() {
+    let alice = cert();
+    let subkey = alice.keys().subkeys().next().unwrap();
+    let binding = subkey.self_signatures().next().unwrap().clone();
+    let mut input = alice.to_vec().unwrap();
+    input.extend(Packet::from(subkey.key().clone()).to_vec().unwrap());
+    input.extend(Packet::from(binding).to_vec().unwrap());
+
+    let fpr = subkey.fingerprint().to_hex();
+    for args in [&[][..], &["--keyring"][..]] {
+        let dump = dump_json(&input, args);
+        let dump = if dump.is_array() { &dump[0] } else { &dump };
+        assert_eq!(
+            dump["subkeys"].as_array().unwrap().len(),
+            alice.keys().subkeys().count()
+        );
+        assert_eq!(dump["duplicate_subkeys"], serde_json::json!([fpr]));
+        assert_eq!(
+            dump["warnings"],
+            serde_json::json!([format!("duplicate subkeys: {fpr}")])
+        );
+    }
+
+    let output = pgpkeydump(&input, &["--strict"]);
+    assert_eq!(output.status.code(), Some(2));
+    let dump = dump_json(&alice.to_vec().unwrap(), &[]);
+    assert_eq!(dump["duplicate_subkeys"], serde_json::json!([]));
+}
+
+#[test]
+fn packets() {
+    let input = cert().armored().to_vec().unwrap();
+
+    let dump = dump_json(&input, &["--packets"]);
+    let packets = dump.as_array().unwrap();
+    assert_eq!(packets[0]["tag"], 6);
+    assert_eq!(packets[0]["name"], "Public-Key Packet");
+    assert!(packets.iter().all(|packet| packet["depth"] == 0));
+
+    // A compressed message nests its literal data packet one level down.
+    let mut message = vec![];
+    {
+        let writer = Message::new(&mut message);
+        let writer = Compressor::new(writer).build().unwrap();
+        let mut writer = LiteralWriter::new(writer).build().unwrap();
+        writer.write_all(b"hello").unwrap();
+        writer.finalize().unwrap();
+    }
+
+    let dump = dump_json(&message, &["--packets"]);
+    let packets = dump.as_array().unwrap();
+    assert_eq!(packets.len(), 2);
+    assert_eq!(packets[0]["tag"], 8);
+    assert_eq!(packets[0]["depth"], 0);
+    assert_eq!(packets[0]["path"], serde_json::json!([0]));
+    assert_eq!(packets[1]["tag"], 11);
+    assert_eq!(packets[1]["depth"], 1);
+    assert_eq!(packets[1]["path"], serde_json::json!([0, 0]));
+}
+
+#[test]
+fn require_armor_short_read() {
+    let input = cert().armored().to_vec().unwrap();
+
+    // Split the armor header across two writes, so that the first read
+    // doesn't see all of it.
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pgpkeydump"))
+        .arg("--require-armor")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(&input[..10]).unwrap();
+    stdin.flush().unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    stdin.write_all(&input[10..]).unwrap();
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let output = pgpkeydump(&cert().to_vec().unwrap(), &["--require-armor"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn key_expiration_timestamp() {
+    let (cert, _) = CertBuilder::general_purpose(None, Some("Alice <alice@example.org>"))
+        .set_validity_period(Duration::from_secs(86400))
+        .generate()
+        .unwrap();
+    let input = cert.armored().to_vec().unwrap();
+
+    let dump = dump_json(&input, &[]);
+    let creation = dump["primary_key"]["creation_timestamp"].as_i64().unwrap();
+    let binding = &dump["userids"][0]["self_signatures"][0];
+    assert_eq!(binding["key_validity_period"], 86400.0);
+    assert_eq!(binding["key_expiration_timestamp"], creation + 86400);
+}
+
+#[test]
+fn trust_signature() {
+    let alice = cert();
+    let userid = alice.userids().next().unwrap().userid().clone();
+    let tsig = SignatureBuilder::new(SignatureType::GenericCertification)
+        .set_trust_signature(1, 120)
+        .unwrap()
+        .set_regular_expression("<[^>]+[@.]example\\.org>$")
+        .unwrap()
+        .add_regular_expression("<[^>]+[@.]example\\.com>$")
+        .unwrap()
+        .sign_userid_binding(&mut signer(&cert()), alice.primary_key().key(), &userid)
+        .unwrap();
+    let alice = alice.insert_packets(tsig).unwrap();
+
+    let dump = dump_json(&alice.to_vec().unwrap(), &[]);
+    let tsig = &dump["userids"][0]["certifications"][0];
+    assert_eq!(tsig["trust_signature"]["level"], 1);
+    assert_eq!(tsig["trust_signature"]["amount"], 120);
+    assert_eq!(tsig["regular_expression"], "<[^>]+[@.]example\\.org>$");
+    assert_eq!(
+        tsig["additional_regular_expressions"],
+        serde_json::json!(["<[^>]+[@.]example\\.com>$"])
+    );
+}
+
+#[test]
+fn toml_lists() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let alice = cert();
+    let message = dir.join("toml-lists-message.txt");
+    let signers = dir.join("toml-lists-signers.pgp");
+    std::fs::write(&message, b"hello").unwrap();
+    std::fs::write(&signers, alice.to_vec().unwrap()).unwrap();
+
+    let sig = SignatureBuilder::new(SignatureType::Binary)
+        .sign_message(&mut signer(&alice), b"hello")
+        .unwrap();
+    let sig = Packet::from(sig).to_vec().unwrap();
+
+    let toml = |input: &[u8], args: &[&str]| -> toml::Table {
+        let output = pgpkeydump(input, &[args, &["--format", "toml"]].concat());
+        assert!(!output.stdout.is_empty(), "{output:?}");
+        toml::from_str(std::str::from_utf8(&output.stdout).unwrap()).unwrap()
+    };
+
+    let dump = toml(&alice.to_vec().unwrap(), &["--packets"]);
+    assert_eq!(dump["packets"][0]["tag"].as_integer(), Some(6));
+
+    let dump = toml(&sig, &["--signature"]);
+    assert_eq!(dump["signatures"].as_array().unwrap().len(), 1);
+
+    let dump = toml(
+        &sig,
+        &[
+            "--verify",
+            message.to_str().unwrap(),
+            "--signer",
+            signers.to_str().unwrap(),
+        ],
+    );
+    assert_eq!(dump["verifications"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn count_max_input_size() {
+    // Big enough that the limit is only hit partway through the keyring,
+    // after the parser has already handed out some certs.
+    // Read from a file, since `pgpkeydump` stops reading stdin partway.
+    let input = cert().to_vec().unwrap().repeat(256);
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("count-max-input-size.pgp");
+    std::fs::write(&path, &input).unwrap();
+    let path = path.to_str().unwrap();
+
+    let output = pgpkeydump(b"", &["--count", path]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "256");
+
+    let limit = (input.len() / 2).to_string();
+    let output = pgpkeydump(
+        b"",
+        &["--count", "--json-errors", "--max-input-size", &limit, path],
+    );
+    assert!(!output.status.success());
+    let error: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(error["kind"], "too-large");
+}
+
+#[test]
+fn hardened_policy() {
+    let (cert, _) = CertBuilder::new()
+        .set_cipher_suite(CipherSuite::RSA2k)
+        .add_userid("Alice <alice@example.org>")
+        .generate()
+        .unwrap();
+    let input = cert.to_vec().unwrap();
+
+    let dump = dump_json(&input, &["--policy", "standard"]);
+    assert_eq!(dump["primary_key"]["status"]["alive"], true);
+
+    let dump = dump_json(&input, &["--policy", "hardened"]);
+    assert_eq!(dump["primary_key"]["status"]["alive"], false);
+}
+
+#[test]
+fn slim_signatures() {
+    let input = cert().armored().to_vec().unwrap();
+
+    let dump = dump_json(&input, &["--slim-signatures"]);
+    let sig = &dump["userids"][0]["self_signatures"][0];
+    assert!(sig["creation_timestamp"].is_i64());
+    assert!(sig.get("hash_algorithm").is_none());
+    assert!(sig.get("notations").is_none());
+    let backsig = dump["subkeys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|subkey| &subkey["primary_key_binding"])
+        .find(|backsig| !backsig.is_null())
+        .unwrap();
+    assert!(backsig.get("digest_prefix").is_none());
+
+    // The schema covers both shapes.
+    let schema = dump_json(b"", &["--schema"]);
+    let shapes = &schema["definitions"]["DumpableSignatureEntry"]["anyOf"];
+    assert_eq!(shapes.as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn key_algorithm_names() {
+    let input = cert().armored().to_vec().unwrap();
+
+    let dump = dump_json(&input, &[]);
+    let primary = &dump["primary_key"];
+    assert_eq!(primary["algorithm"], "EdDSA");
+    assert_eq!(
+        primary["algorithm_name"],
+        "EdDSA Edwards-curve Digital Signature Algorithm"
+    );
+    assert_eq!(primary["algorithm_id"], 22);
+    // The same short form as signatures use.
+    assert_eq!(
+        primary["algorithm"],
+        dump["userids"][0]["self_signatures"][0]["algorithm"]
+    );
+}